@@ -0,0 +1,59 @@
+//! FIXME: write short doc here
+
+use rustc_hash::FxHashMap;
+
+use ra_text_edit::TextEditBuilder;
+
+use crate::{SyntaxElement, SyntaxNode};
+
+/// Finds a (potentially) minimal diff, which, applied to `from`, produces `to`.
+///
+/// The diff is a top-down structural comparison: if two elements are equal
+/// (same `SyntaxKind`, same shape, same token text -- `SyntaxElement`'s
+/// `PartialEq` is green-node structural equality, which is cheap since green
+/// nodes are interned), we stop descending and emit no edit for that subtree
+/// at all. Otherwise, if the nodes line up (same kind, same number of
+/// children) we recurse into the children pairwise; only when that isn't
+/// possible (kinds differ, child counts differ, or we bottom out at a token)
+/// do we record a replacement over that element's `text_range()`.
+///
+/// Edits stay confined to the subtrees that changed, but a node whose number
+/// of children changed is not diffed any further: `{ a, b }` -> `{ a, b, c }`
+/// replaces the whole record field list.
+pub fn diff(from: &SyntaxNode, to: &SyntaxNode) -> Diff {
+    let mut diff = Diff { replacements: FxHashMap::default() };
+    go(&mut diff, from.clone().into(), to.clone().into());
+    diff
+}
+
+#[derive(Debug)]
+pub struct Diff {
+    replacements: FxHashMap<SyntaxElement, SyntaxElement>,
+}
+
+impl Diff {
+    pub fn into_text_edit(self, builder: &mut TextEditBuilder) {
+        for (from, to) in self.replacements {
+            builder.replace(from.text_range(), to.to_string())
+        }
+    }
+}
+
+fn go(diff: &mut Diff, lhs: SyntaxElement, rhs: SyntaxElement) {
+    if lhs == rhs {
+        return;
+    }
+    if let (Some(lhs_node), Some(rhs_node)) = (lhs.as_node(), rhs.as_node()) {
+        let lhs_children = lhs_node.children_with_tokens().count();
+        let rhs_children = rhs_node.children_with_tokens().count();
+        if lhs.kind() == rhs.kind() && lhs_children == rhs_children {
+            for (lhs_child, rhs_child) in
+                lhs_node.children_with_tokens().zip(rhs_node.children_with_tokens())
+            {
+                go(diff, lhs_child, rhs_child);
+            }
+            return;
+        }
+    }
+    diff.replacements.insert(lhs, rhs);
+}
@@ -1,14 +1,17 @@
 //! This module contains functions for editing syntax trees. As the trees are
 //! immutable, all function here return a fresh copy of the tree, instead of
 //! doing an in-place modification.
+use std::{fmt, iter, ops::RangeInclusive};
 
 use arrayvec::ArrayVec;
-use std::ops::RangeInclusive;
+use rustc_hash::FxHashMap;
 
 use crate::{
     algo,
-    ast::{self, make, AstNode},
-    InsertPosition, SyntaxElement,
+    ast::{self, make, AstNode, TypeBoundsOwner},
+    Direction, InsertPosition, SyntaxElement,
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
+    SyntaxNode, T,
 };
 
 impl ast::FnDef {
@@ -23,7 +26,7 @@ impl ast::FnDef {
         } else {
             to_insert.push(make::tokens::single_space().into());
             to_insert.push(body.syntax().clone().into());
-            return insert_children(self, InsertPosition::Last, to_insert.into_iter());
+            return insert_element(self, InsertPosition::Last, to_insert);
         };
         to_insert.push(body.syntax().clone().into());
         let replace_range = RangeInclusive::new(old_body_or_semi.clone(), old_body_or_semi);
@@ -31,13 +34,260 @@ impl ast::FnDef {
     }
 }
 
+impl ast::RecordFieldList {
+    #[must_use]
+    pub fn append_field(&self, field: &ast::RecordField) -> ast::RecordFieldList {
+        self.insert_field(InsertPosition::Last, field)
+    }
+
+    #[must_use]
+    pub fn insert_field(
+        &self,
+        position: InsertPosition<&'_ ast::RecordField>,
+        field: &ast::RecordField,
+    ) -> ast::RecordFieldList {
+        let is_multiline = self.syntax().text().contains_char('\n');
+        let ws;
+        let field_indent = IndentLevel::from_node(self.syntax()) + 1;
+        let space = if is_multiline {
+            ws = make::tokens::WsBuilder::new(&format!("\n{}", field_indent));
+            ws.ws()
+        } else {
+            make::tokens::single_space()
+        };
+        let field = field.indent(field_indent);
+
+        let mut to_insert: ArrayVec<[SyntaxElement; 4]> = ArrayVec::new();
+        to_insert.push(space.into());
+        to_insert.push(field.syntax().clone().into());
+        to_insert.push(make::tokens::comma().into());
+
+        match position {
+            InsertPosition::First => {
+                let l_curly = match self.l_curly() {
+                    Some(it) => it,
+                    None => return self.clone(),
+                };
+                insert_element(self, InsertPosition::After(l_curly), to_insert)
+            }
+            InsertPosition::Last => {
+                if !is_multiline {
+                    // don't insert comma before curly
+                    to_insert.pop();
+                }
+                match self.fields().last() {
+                    Some(it) => append_item_separated(self, it.syntax().clone().into(), to_insert),
+                    None => {
+                        let l_curly = match self.l_curly() {
+                            Some(it) => it,
+                            None => return self.clone(),
+                        };
+                        insert_element(self, InsertPosition::After(l_curly), to_insert)
+                    }
+                }
+            }
+            InsertPosition::Before(anchor) => {
+                insert_element(self, InsertPosition::Before(anchor.syntax().clone().into()), to_insert)
+            }
+            InsertPosition::After(anchor) => {
+                append_item_separated(self, anchor.syntax().clone().into(), to_insert)
+            }
+        }
+    }
+
+    fn l_curly(&self) -> Option<SyntaxElement> {
+        self.syntax().children_with_tokens().find(|it| it.kind() == T!['{'])
+    }
+}
+
+impl ast::ItemList {
+    #[must_use]
+    pub fn append_items(&self, items: impl Iterator<Item = ast::ImplItem>) -> ast::ItemList {
+        let mut res = self.clone();
+        if !self.syntax().text().contains_char('\n') {
+            res = res.make_multiline();
+        }
+        items.fold(res, |acc, it| acc.append_item(it))
+    }
+
+    #[must_use]
+    pub fn append_item(&self, item: ast::ImplItem) -> ast::ItemList {
+        let (indent, position) = match self.impl_items().last() {
+            Some(it) => (
+                IndentLevel::from_node(it.syntax()),
+                InsertPosition::After(it.syntax().clone().into()),
+            ),
+            None => match self.l_curly() {
+                Some(it) => (IndentLevel::from_node(self.syntax()) + 1, InsertPosition::After(it)),
+                None => return self.clone(),
+            },
+        };
+        let item = item.indent(indent);
+        let ws = make::tokens::WsBuilder::new(&format!("\n{}", indent));
+        let to_insert: ArrayVec<[SyntaxElement; 2]> =
+            [ws.ws().into(), item.syntax().clone().into()].into();
+        insert_element(self, position, to_insert)
+    }
+
+    fn make_multiline(&self) -> ast::ItemList {
+        make_multiline(self)
+    }
+
+    fn l_curly(&self) -> Option<SyntaxElement> {
+        self.syntax().children_with_tokens().find(|it| it.kind() == T!['{'])
+    }
+}
+
+impl ast::ImplItem {
+    #[must_use]
+    pub fn strip_attrs_and_docs(&self) -> ast::ImplItem {
+        let mut res = self.clone();
+        while let Some(start) =
+            res.syntax().children_with_tokens().find(|it| it.kind() == ATTR || it.kind() == COMMENT)
+        {
+            let end = match start.next_sibling_or_token() {
+                Some(el) if el.kind() == WHITESPACE => el,
+                Some(_) | None => start.clone(),
+            };
+            res = remove_range(&res, RangeInclusive::new(start, end));
+        }
+        res
+    }
+}
+
+impl ast::TypeParam {
+    #[must_use]
+    pub fn remove_bounds(&self) -> ast::TypeParam {
+        let colon = match self.colon_token() {
+            Some(it) => it,
+            None => return self.clone(),
+        };
+        let end = match self.type_bound_list() {
+            Some(it) => it.syntax().clone().into(),
+            None => colon.clone().into(),
+        };
+        remove_range(self, RangeInclusive::new(colon.into(), end))
+    }
+}
+
+/// Turns `{ ` (single-line braces) into `{\n<indent>` so subsequent items can
+/// be appended on their own line.
+fn make_multiline<N: AstNode>(node: &N) -> N {
+    let l_curly = match node.syntax().children_with_tokens().find(|it| it.kind() == T!['{']) {
+        Some(it) => it,
+        None => return node.clone(),
+    };
+    let sibling = match l_curly.next_sibling_or_token() {
+        Some(it) => it,
+        None => return node.clone(),
+    };
+    let existing_ws = match sibling.as_token() {
+        None => None,
+        Some(tok) if tok.kind() != WHITESPACE => None,
+        Some(ws) => {
+            if ws.text().contains('\n') {
+                return node.clone();
+            }
+            Some(ws.clone())
+        }
+    };
+
+    let indent = IndentLevel::from_node(node.syntax());
+    let ws = make::tokens::WsBuilder::new(&format!("\n{}", indent));
+    let to_insert = iter::once(ws.ws().into());
+    match existing_ws {
+        None => insert_element(node, InsertPosition::After(l_curly), to_insert),
+        Some(ws) => {
+            replace_children(node, RangeInclusive::new(ws.clone().into(), ws.into()), to_insert)
+        }
+    }
+}
+
+/// Amount of indentation, in units of 4 spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IndentLevel(pub u8);
+
+impl fmt::Display for IndentLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for _ in 0..self.0 {
+            write!(f, "    ")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Add<u8> for IndentLevel {
+    type Output = IndentLevel;
+    fn add(self, amount: u8) -> IndentLevel {
+        IndentLevel(self.0 + amount)
+    }
+}
+
+impl IndentLevel {
+    /// The indentation of `node`'s own line, read off its leading whitespace.
+    pub fn from_node(node: &SyntaxNode) -> IndentLevel {
+        let mut token = node.first_token().and_then(|it| it.prev_token());
+        while let Some(tok) = token {
+            if tok.kind() == WHITESPACE {
+                if let Some(pos) = tok.text().rfind('\n') {
+                    return IndentLevel((tok.text()[pos + 1..].chars().count() / 4) as u8);
+                }
+            }
+            token = tok.prev_token();
+        }
+        IndentLevel(0)
+    }
+}
+
+/// Re-indents every multi-line node, by rewriting each whitespace token that
+/// contains a newline.
+pub trait AstNodeEdit: AstNode + Clone + Sized {
+    #[must_use]
+    fn indent(&self, level: IndentLevel) -> Self {
+        if level.0 == 0 {
+            return self.clone();
+        }
+        Self::cast(reindent(self.syntax(), |text| text.replace('\n', &format!("\n{}", level))))
+            .unwrap()
+    }
+
+    #[must_use]
+    fn dedent(&self, level: IndentLevel) -> Self {
+        if level.0 == 0 {
+            return self.clone();
+        }
+        let undent = level.to_string();
+        Self::cast(reindent(self.syntax(), |text| text.replace(&format!("\n{}", undent), "\n")))
+            .unwrap()
+    }
+}
+
+impl<N: AstNode + Clone> AstNodeEdit for N {}
+
+fn reindent(node: &SyntaxNode, f: impl Fn(&str) -> String) -> SyntaxNode {
+    let replacements: FxHashMap<SyntaxElement, SyntaxElement> = node
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == WHITESPACE && it.text().contains('\n'))
+        .map(|ws| {
+            let new_ws = make::tokens::WsBuilder::new(&f(ws.text()));
+            (ws.into(), new_ws.ws().into())
+        })
+        .collect();
+    algo::replace_descendants(node, &replacements)
+}
+
+/// Inserts `to_insert` into `node` at `position`, returning a fresh node of
+/// the same kind. This is the generic building block every other insertion
+/// helper in this module is written in terms of.
 #[must_use]
-fn insert_children<N: AstNode>(
-    parent: &N,
+pub fn insert_element<N: AstNode>(
+    node: &N,
     position: InsertPosition<SyntaxElement>,
-    mut to_insert: impl Iterator<Item = SyntaxElement>,
+    to_insert: impl IntoIterator<Item = SyntaxElement>,
 ) -> N {
-    let new_syntax = algo::insert_children(parent.syntax(), position, &mut to_insert);
+    let mut to_insert = to_insert.into_iter();
+    let new_syntax = algo::insert_children(node.syntax(), position, &mut to_insert);
     N::cast(new_syntax).unwrap()
 }
 
@@ -50,3 +300,32 @@ fn replace_children<N: AstNode>(
     let new_syntax = algo::replace_children(parent.syntax(), to_replace, &mut to_insert);
     N::cast(new_syntax).unwrap()
 }
+
+/// Deletes `to_remove` (an inclusive range of siblings) from `node`,
+/// returning a fresh node of the same kind. The generic counterpart of
+/// [`insert_element`], for assists like `TypeParam::remove_bounds` that only
+/// ever delete a span, with nothing to splice back in.
+#[must_use]
+pub fn remove_range<N: AstNode>(node: &N, to_remove: RangeInclusive<SyntaxElement>) -> N {
+    replace_children(node, to_remove, iter::empty())
+}
+
+/// Appends `to_insert` right after `after`, an existing element of a
+/// comma-separated list. Threads the comma bookkeeping shared by every such
+/// list: an existing trailing comma after `after` is reused, otherwise one
+/// is inserted, so callers like `RecordFieldList::insert_field` don't have
+/// to re-derive the comma anchoring rules themselves.
+#[must_use]
+pub fn append_item_separated<N: AstNode>(
+    node: &N,
+    after: SyntaxElement,
+    to_insert: impl IntoIterator<Item = SyntaxElement>,
+) -> N {
+    match after.siblings_with_tokens(Direction::Next).find(|it| it.kind() == T![,]) {
+        Some(comma) => insert_element(node, InsertPosition::After(comma), to_insert),
+        None => {
+            let to_insert = iter::once(make::tokens::comma().into()).chain(to_insert);
+            insert_element(node, InsertPosition::After(after), to_insert)
+        }
+    }
+}